@@ -1,107 +1,329 @@
+use std::collections::HashMap;
+use std::sync::mpsc;
 use std::sync::Arc;
 use std::time::Instant;
 
 
+use bevy_ecs::prelude::*;
 use winit::{
     application::ApplicationHandler,
-    error::EventLoopError,
-    event::WindowEvent,
-    event_loop::{self, ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy},
-    window::{Window, WindowId},
+    event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy},
+    keyboard::{KeyCode, PhysicalKey},
+    window::{Fullscreen, Window, WindowAttributes, WindowId},
     dpi::PhysicalSize
 };
 
 
+use crate::game::Game;
+use crate::input::Input;
 use crate::render::graphics::{create_graphics, Graphics, Rc};
+use crate::time::FrameTime;
+use crate::window::WindowConfig;
+
+/// Fired into the `World` whenever the primary window is resized, so systems
+/// can react without the engine needing to know about them.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ResizeEvent(pub PhysicalSize<u32>);
+
+/// Default fixed simulation timestep (60Hz), for passing as `sim_dt` to
+/// `EngineContext::new`, which always takes the timestep explicitly.
+pub const DEFAULT_SIM_DT: f32 = 1.0 / 60.0;
+
+/// Upper bound on the per-frame delta fed into the accumulator. Without this,
+/// a stalled window (e.g. the user dragging/resizing it) would hand the
+/// simulation a huge `dt` on the next frame, which in turn takes so many fixed
+/// steps to drain that the next frame is late too -- the "spiral of death".
+const MAX_FRAME_DT: f32 = 0.1;
+
+/// Sent through the event loop's `EventLoopProxy` to ask the running app to do
+/// something from outside a window event handler. Modeled on kludgine's
+/// `RuntimeRequest`.
+pub enum RuntimeRequest {
+    /// Delivered once the async graphics/window setup for a window completes.
+    GraphicsReady(Graphics),
+    /// Asks the event loop to create another window -- used for tool or
+    /// inspector windows alongside the main view. `reply` is sent the new
+    /// window's id once the window has been created; its `Graphics` is
+    /// registered separately, when the matching `GraphicsReady` arrives.
+    OpenWindow {
+        attributes: WindowAttributes,
+        reply: mpsc::Sender<WindowId>,
+    },
+}
+
+/// Lets game systems ask the engine to open an additional window at runtime.
+/// Inserted into the `World` as a resource by `App::new`.
+#[derive(Resource, Clone)]
+pub struct WindowOpener {
+    proxy: EventLoopProxy<RuntimeRequest>,
+}
 
-enum State {
-    Ready(Graphics),
-    Init(Option<EventLoopProxy<Graphics>>),
+impl WindowOpener {
+    /// Requests a new window with the given `attributes`. Returns a receiver
+    /// that yields the new window's id once the window has been created.
+    pub fn open_window(&self, attributes: WindowAttributes) -> mpsc::Receiver<WindowId> {
+        let (reply, rx) = mpsc::channel();
+        let _ = self
+            .proxy
+            .send_event(RuntimeRequest::OpenWindow { attributes, reply });
+        rx
+    }
 }
 
 pub struct EngineContext {
-    pub delta_time: f32,
-    event_loop: Option<EventLoop<Graphics>>,
+    event_loop: Option<EventLoop<RuntimeRequest>>,
     app: App,
 }
 
 pub struct App {
-    state: State,
+    proxy: EventLoopProxy<RuntimeRequest>,
+    /// The window driving the engine's ECS update/render schedules; its
+    /// `Graphics` lives in `world` as a non-send resource (see
+    /// `render::graphics::Graphics`) rather than in `windows`.
+    primary_window: Option<WindowId>,
+    /// Secondary (e.g. tool/inspector) windows, drawn directly without going
+    /// through the ECS schedules.
+    windows: HashMap<WindowId, Graphics>,
+    window_config: WindowConfig,
+    world: World,
+    update_schedule: Schedule,
+    render_schedule: Schedule,
+    sim_dt: f32,
+    accumulator: f32,
+    last_instant: Option<Instant>,
 }
 
 
 impl App {
-    pub fn new(event_loop: &EventLoop<Graphics>) -> Self {
+    pub fn new(
+        event_loop: &EventLoop<RuntimeRequest>,
+        mut game: impl Game,
+        sim_dt: f32,
+        window_config: WindowConfig,
+    ) -> Self {
+        let proxy = event_loop.create_proxy();
+
+        let mut world = World::new();
+        world.init_resource::<FrameTime>();
+        world.init_resource::<Input>();
+        world.init_resource::<Events<ResizeEvent>>();
+        world.insert_resource(WindowOpener {
+            proxy: proxy.clone(),
+        });
+
+        let mut update_schedule = Schedule::default();
+        let mut render_schedule = Schedule::default();
+        game.build(&mut world, &mut update_schedule, &mut render_schedule);
+
         Self {
-            state: State::Init(Some(event_loop.create_proxy())),
+            proxy,
+            primary_window: None,
+            windows: HashMap::new(),
+            window_config,
+            world,
+            update_schedule,
+            render_schedule,
+            sim_dt,
+            accumulator: 0.0,
+            last_instant: None,
         }
     }
 
-    fn draw(&mut self) {
-        if let State::Ready(gfx) = &mut self.state {
+    fn create_window(&self, event_loop: &ActiveEventLoop, attributes: WindowAttributes) -> WindowId {
+        let window = Rc::new(
+            event_loop
+                .create_window(attributes)
+                .expect("Failed to create a window."),
+        );
+        let id = window.id();
+
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(create_graphics(window, self.proxy.clone()));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        pollster::block_on(create_graphics(window, self.proxy.clone()));
+
+        id
+    }
+
+    fn redraw(&mut self, window_id: WindowId) {
+        if Some(window_id) == self.primary_window {
+            if self.world.get_non_send_resource::<Graphics>().is_none() {
+                return;
+            }
+
+            let now = Instant::now();
+            let frame_dt = match self.last_instant {
+                Some(last) => (now - last).as_secs_f32(),
+                None => 0.0,
+            };
+            self.last_instant = Some(now);
+
+            self.accumulator += frame_dt.min(MAX_FRAME_DT);
+            self.world.resource_mut::<Events<ResizeEvent>>().update();
+            self.world.resource_mut::<FrameTime>().dt = self.sim_dt;
+
+            while self.accumulator >= self.sim_dt {
+                self.update_schedule.run(&mut self.world);
+                self.world.resource_mut::<Input>().end_tick();
+                self.accumulator -= self.sim_dt;
+            }
+
+            self.world.resource_mut::<FrameTime>().alpha = self.accumulator / self.sim_dt;
+            self.render_schedule.run(&mut self.world);
+
+            if let Some(mut gfx) = self.world.get_non_send_resource_mut::<Graphics>() {
+                gfx.draw();
+                // `ControlFlow::Poll` doesn't auto-emit `RedrawRequested`, so
+                // the engine has to ask for the next one itself or the
+                // simulation would freeze after this frame.
+                gfx.request_redraw();
+            }
+        } else if let Some(gfx) = self.windows.get_mut(&window_id) {
             gfx.draw();
+            gfx.request_redraw();
         }
     }
 
-    fn resized(&mut self, size: PhysicalSize<u32>) {
-        if let State::Ready(gfx) = &mut self.state {
+    fn resized(&mut self, window_id: WindowId, size: PhysicalSize<u32>) {
+        if Some(window_id) == self.primary_window {
+            if let Some(mut gfx) = self.world.get_non_send_resource_mut::<Graphics>() {
+                gfx.resize(size);
+            }
+            self.world
+                .resource_mut::<Events<ResizeEvent>>()
+                .send(ResizeEvent(size));
+        } else if let Some(gfx) = self.windows.get_mut(&window_id) {
             gfx.resize(size);
         }
     }
-}
 
+    fn keyboard_input(&mut self, window_id: WindowId, event: KeyEvent) {
+        if Some(window_id) != self.primary_window {
+            return;
+        }
 
+        if event.physical_key == PhysicalKey::Code(KeyCode::KeyF)
+            && event.state == ElementState::Pressed
+            && !event.repeat
+        {
+            if let Some(gfx) = self.world.get_non_send_resource::<Graphics>() {
+                toggle_fullscreen(gfx.window());
+            }
+        }
 
+        self.world.resource_mut::<Input>().on_keyboard_input(&event);
+    }
 
+    fn mouse_input(&mut self, window_id: WindowId, button: MouseButton, state: ElementState) {
+        if Some(window_id) == self.primary_window {
+            self.world.resource_mut::<Input>().on_mouse_input(button, state);
+        }
+    }
 
-impl ApplicationHandler<Graphics> for App {
-    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        if let State::Init(proxy) = &mut self.state {
-            if let Some(proxy) = proxy.take() {
-                let mut win_attr = Window::default_attributes();
+    fn cursor_moved(&mut self, window_id: WindowId, position: (f64, f64)) {
+        if Some(window_id) == self.primary_window {
+            self.world.resource_mut::<Input>().on_cursor_moved(position);
+        }
+    }
 
-                #[cfg(not(target_arch = "wasm32"))]
-                {
-                    win_attr = win_attr.with_title("what");
-                }
+    fn mouse_wheel(&mut self, window_id: WindowId, delta: MouseScrollDelta) {
+        if Some(window_id) == self.primary_window {
+            self.world.resource_mut::<Input>().on_mouse_wheel(delta);
+        }
+    }
+}
 
-                #[cfg(target_arch = "wasm32")]
-                {
-                    use winit::platform::web::WindowAttributesExtWebSys;
-                    win_attr = win_attr.with_append(true);
-                }
+#[cfg(not(target_arch = "wasm32"))]
+fn toggle_fullscreen(window: &Window) {
+    window.set_fullscreen(match window.fullscreen() {
+        Some(_) => None,
+        None => Some(Fullscreen::Borderless(None)),
+    });
+}
 
-                let window = Rc::new(
-                    event_loop
-                        .create_window(win_attr)
-                        .expect("Failed to create a window."),
-                );
+#[cfg(target_arch = "wasm32")]
+fn toggle_fullscreen(window: &Window) {
+    // `Window::set_fullscreen` isn't wired up to the browser's own fullscreen
+    // API on wasm, so go through the canvas element directly instead, like
+    // the wgpu-game-of-life example does.
+    use winit::platform::web::WindowExtWebSys;
+
+    let Some(canvas) = window.canvas() else {
+        return;
+    };
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+
+    if document.fullscreen_element().is_some() {
+        document.exit_fullscreen();
+    } else {
+        let _ = canvas.request_fullscreen();
+    }
+}
 
-                #[cfg(target_arch = "wasm32")]
-                wasm_bindgen_futures::spawn_local(create_graphics(window, proxy));
 
-                #[cfg(not(target_arch = "wasm32"))]
-                pollster::block_on(create_graphics(window, proxy));
-            }
+
+
+impl ApplicationHandler<RuntimeRequest> for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.primary_window.is_none() {
+            let win_attr = self.window_config.to_attributes();
+            let id = self.create_window(event_loop, win_attr);
+            self.primary_window = Some(id);
         }
     }
 
-    fn user_event(&mut self, _event_loop: &ActiveEventLoop, graphics: Graphics) {
-        // Request a redraw now that graphics are ready
-        graphics.request_redraw();
-        self.state = State::Ready(graphics);
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, request: RuntimeRequest) {
+        match request {
+            RuntimeRequest::GraphicsReady(graphics) => {
+                let id = graphics.window_id();
+                graphics.request_redraw();
+                if Some(id) == self.primary_window {
+                    self.world.insert_non_send_resource(graphics);
+                } else {
+                    self.windows.insert(id, graphics);
+                }
+            }
+            RuntimeRequest::OpenWindow { attributes, reply } => {
+                let id = self.create_window(event_loop, attributes);
+                let _ = reply.send(id);
+            }
+        }
     }
 
     fn window_event(
         &mut self,
         event_loop: &ActiveEventLoop,
-        _window_id: WindowId,
+        window_id: WindowId,
         event: WindowEvent,
     ) {
         match event {
-            WindowEvent::Resized(size) => self.resized(size),
-            WindowEvent::RedrawRequested => self.draw(),
-            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::Resized(size) => self.resized(window_id, size),
+            WindowEvent::RedrawRequested => self.redraw(window_id),
+            WindowEvent::KeyboardInput { event, .. } => self.keyboard_input(window_id, event),
+            WindowEvent::MouseInput { button, state, .. } => {
+                self.mouse_input(window_id, button, state)
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor_moved(window_id, (position.x, position.y))
+            }
+            WindowEvent::MouseWheel { delta, .. } => self.mouse_wheel(window_id, delta),
+            WindowEvent::CloseRequested => {
+                if Some(window_id) == self.primary_window {
+                    self.world.remove_non_send_resource::<Graphics>();
+                    self.primary_window = None;
+                } else {
+                    self.windows.remove(&window_id);
+                }
+
+                // Only exit once every window -- primary and secondary -- has closed.
+                if self.primary_window.is_none() && self.windows.is_empty() {
+                    event_loop.exit();
+                }
+            }
             _ => {}
         }
     }
@@ -111,7 +333,7 @@ impl ApplicationHandler<Graphics> for App {
 
 
 #[cfg(target_arch = "wasm32")]
-fn run_app(event_loop: EventLoop<Graphics>, app: App) {
+fn run_app(event_loop: EventLoop<RuntimeRequest>, app: App) {
     // Sets up panics to go to the console.error in browser environments
     std::panic::set_hook(Box::new(console_error_panic_hook::hook));
     console_log::init_with_level(log::Level::Error).expect("Couldn't initialize logger");
@@ -124,7 +346,7 @@ fn run_app(event_loop: EventLoop<Graphics>, app: App) {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn run_app(event_loop: EventLoop<Graphics>, mut app: App) {
+fn run_app(event_loop: EventLoop<RuntimeRequest>, mut app: App) {
     // Allows the setting of the log level through RUST_LOG env var.
     // It also allows wgpu logs to be seen.
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("error")).init();
@@ -136,12 +358,14 @@ fn run_app(event_loop: EventLoop<Graphics>, mut app: App) {
 
 
 impl EngineContext {
-    pub fn new() -> Arc<Self> {
-        let event_loop = EventLoop::<Graphics>::with_user_event().build().unwrap();
+    /// Creates a new engine context that will drive `game` at a fixed
+    /// simulation timestep of `sim_dt` seconds per step (e.g. `1.0 / 60.0`),
+    /// creating its primary window according to `window_config`.
+    pub fn new(game: impl Game, sim_dt: f32, window_config: WindowConfig) -> Arc<Self> {
+        let event_loop = EventLoop::<RuntimeRequest>::with_user_event().build().unwrap();
         event_loop.set_control_flow(ControlFlow::Poll);
-        let app = App::new(&event_loop);
+        let app = App::new(&event_loop, game, sim_dt, window_config);
         Arc::new(Self {
-            delta_time: 0.0,
             event_loop: Some(event_loop),
             app: app,
         })
@@ -0,0 +1,13 @@
+use bevy_ecs::prelude::*;
+
+/// Hook for wiring user game logic into the engine's ECS `World`.
+///
+/// `build` is called once, after the frame-timing and input resources have
+/// been inserted into `world` but before the engine starts ticking. The
+/// `Graphics` non-send resource is not present yet: it is inserted later,
+/// once the primary window's graphics setup completes.
+/// Spawn your initial entities here and add systems to `update`/`render` --
+/// the engine owns running those schedules every tick/redraw afterwards.
+pub trait Game {
+    fn build(&mut self, world: &mut World, update: &mut Schedule, render: &mut Schedule);
+}
@@ -0,0 +1,215 @@
+use std::collections::HashSet;
+
+use bevy_ecs::prelude::*;
+use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta};
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+/// Per-frame keyboard/mouse state, queryable by game systems as a resource.
+///
+/// "Just pressed"/"just released" sets hold for a single fixed update tick --
+/// `App` clears them via `end_tick` right after running `update_schedule`.
+#[derive(Resource, Default)]
+pub struct Input {
+    keys_held: HashSet<KeyCode>,
+    keys_pressed: HashSet<KeyCode>,
+    keys_released: HashSet<KeyCode>,
+    mouse_held: HashSet<MouseButton>,
+    mouse_pressed: HashSet<MouseButton>,
+    mouse_released: HashSet<MouseButton>,
+    cursor_pos: Option<(f64, f64)>,
+    scroll_delta: (f32, f32),
+}
+
+impl Input {
+    pub fn is_key_held(&self, key: KeyCode) -> bool {
+        self.keys_held.contains(&key)
+    }
+
+    pub fn is_key_pressed(&self, key: KeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    pub fn is_key_released(&self, key: KeyCode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    pub fn is_mouse_held(&self, button: MouseButton) -> bool {
+        self.mouse_held.contains(&button)
+    }
+
+    pub fn is_mouse_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_pressed.contains(&button)
+    }
+
+    pub fn is_mouse_released(&self, button: MouseButton) -> bool {
+        self.mouse_released.contains(&button)
+    }
+
+    pub fn cursor_position(&self) -> Option<(f64, f64)> {
+        self.cursor_pos
+    }
+
+    pub fn scroll_delta(&self) -> (f32, f32) {
+        self.scroll_delta
+    }
+
+    /// Clears the "just pressed"/"just released" sets and the scroll delta.
+    /// Called once at the end of each fixed update tick, after systems have
+    /// had a chance to observe this tick's input -- clearing it at the start
+    /// would wipe out events gathered since the previous tick before any
+    /// system saw them.
+    pub(crate) fn end_tick(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.mouse_pressed.clear();
+        self.mouse_released.clear();
+        self.scroll_delta = (0.0, 0.0);
+    }
+
+    pub(crate) fn on_keyboard_input(&mut self, event: &KeyEvent) {
+        let PhysicalKey::Code(code) = event.physical_key else {
+            return;
+        };
+        self.handle_key(code, event.state, event.repeat);
+    }
+
+    /// The actual press/hold/release state machine, split out from
+    /// `on_keyboard_input` so it can be exercised with plain `KeyCode`s
+    /// instead of a `winit::event::KeyEvent` (which can't be constructed
+    /// outside of winit itself).
+    fn handle_key(&mut self, code: KeyCode, state: ElementState, repeat: bool) {
+        match state {
+            ElementState::Pressed => {
+                if !repeat && self.keys_held.insert(code) {
+                    self.keys_pressed.insert(code);
+                }
+            }
+            ElementState::Released => {
+                self.keys_held.remove(&code);
+                self.keys_released.insert(code);
+            }
+        }
+    }
+
+    pub(crate) fn on_mouse_input(&mut self, button: MouseButton, state: ElementState) {
+        match state {
+            ElementState::Pressed => {
+                if self.mouse_held.insert(button) {
+                    self.mouse_pressed.insert(button);
+                }
+            }
+            ElementState::Released => {
+                self.mouse_held.remove(&button);
+                self.mouse_released.insert(button);
+            }
+        }
+    }
+
+    pub(crate) fn on_cursor_moved(&mut self, position: (f64, f64)) {
+        self.cursor_pos = Some(position);
+    }
+
+    pub(crate) fn on_mouse_wheel(&mut self, delta: MouseScrollDelta) {
+        let (dx, dy) = match delta {
+            MouseScrollDelta::LineDelta(x, y) => (x, y),
+            MouseScrollDelta::PixelDelta(pos) => (pos.x as f32, pos.y as f32),
+        };
+        self.scroll_delta.0 += dx;
+        self.scroll_delta.1 += dy;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::dpi::PhysicalPosition;
+
+    use super::*;
+
+    #[test]
+    fn key_press_sets_held_and_pressed() {
+        let mut input = Input::default();
+        input.handle_key(KeyCode::Space, ElementState::Pressed, false);
+
+        assert!(input.is_key_held(KeyCode::Space));
+        assert!(input.is_key_pressed(KeyCode::Space));
+        assert!(!input.is_key_released(KeyCode::Space));
+    }
+
+    #[test]
+    fn key_repeat_stays_held_without_re_triggering_pressed() {
+        let mut input = Input::default();
+        input.handle_key(KeyCode::Space, ElementState::Pressed, false);
+        input.end_tick();
+        input.handle_key(KeyCode::Space, ElementState::Pressed, true);
+
+        assert!(input.is_key_held(KeyCode::Space));
+        assert!(!input.is_key_pressed(KeyCode::Space));
+    }
+
+    #[test]
+    fn key_release_clears_held_and_sets_released() {
+        let mut input = Input::default();
+        input.handle_key(KeyCode::Space, ElementState::Pressed, false);
+        input.handle_key(KeyCode::Space, ElementState::Released, false);
+
+        assert!(!input.is_key_held(KeyCode::Space));
+        assert!(input.is_key_released(KeyCode::Space));
+    }
+
+    #[test]
+    fn end_tick_clears_just_pressed_and_released_but_not_held() {
+        let mut input = Input::default();
+        input.handle_key(KeyCode::Space, ElementState::Pressed, false);
+        input.handle_key(KeyCode::KeyW, ElementState::Pressed, false);
+        input.handle_key(KeyCode::KeyW, ElementState::Released, false);
+        input.end_tick();
+
+        assert!(input.is_key_held(KeyCode::Space));
+        assert!(!input.is_key_pressed(KeyCode::Space));
+        assert!(!input.is_key_released(KeyCode::KeyW));
+    }
+
+    #[test]
+    fn mouse_press_and_release_transitions() {
+        let mut input = Input::default();
+        input.on_mouse_input(MouseButton::Left, ElementState::Pressed);
+
+        assert!(input.is_mouse_held(MouseButton::Left));
+        assert!(input.is_mouse_pressed(MouseButton::Left));
+
+        input.end_tick();
+        input.on_mouse_input(MouseButton::Left, ElementState::Released);
+
+        assert!(!input.is_mouse_held(MouseButton::Left));
+        assert!(input.is_mouse_released(MouseButton::Left));
+    }
+
+    #[test]
+    fn cursor_moved_tracks_latest_position() {
+        let mut input = Input::default();
+        assert_eq!(input.cursor_position(), None);
+
+        input.on_cursor_moved((1.0, 2.0));
+        input.on_cursor_moved((3.0, 4.0));
+
+        assert_eq!(input.cursor_position(), Some((3.0, 4.0)));
+    }
+
+    #[test]
+    fn scroll_delta_accumulates_across_line_and_pixel_events() {
+        let mut input = Input::default();
+        input.on_mouse_wheel(MouseScrollDelta::LineDelta(1.0, 2.0));
+        input.on_mouse_wheel(MouseScrollDelta::PixelDelta(PhysicalPosition::new(3.0, 4.0)));
+
+        assert_eq!(input.scroll_delta(), (4.0, 6.0));
+    }
+
+    #[test]
+    fn end_tick_resets_scroll_delta() {
+        let mut input = Input::default();
+        input.on_mouse_wheel(MouseScrollDelta::LineDelta(1.0, 2.0));
+        input.end_tick();
+
+        assert_eq!(input.scroll_delta(), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,12 @@
+mod game;
+
+pub mod context;
+pub mod input;
+pub mod render;
+pub mod time;
+pub mod window;
+
+pub use bevy_ecs;
+pub use context::EngineContext;
+pub use game::Game;
+pub use window::WindowConfig;
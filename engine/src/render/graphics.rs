@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use winit::dpi::PhysicalSize;
+use winit::event_loop::EventLoopProxy;
+use winit::window::{Window, WindowId};
+
+use crate::context::RuntimeRequest;
+
+/// Handle to the window a [`Graphics`] instance owns, shared with the
+/// windowing code that created it. Named `Rc` for historical reasons -- it's
+/// actually an `Arc` since the `Graphics` it's embedded in crosses the
+/// `EventLoopProxy` channel into `user_event`.
+pub type Rc<T> = Arc<T>;
+
+/// Per-window wgpu state: the live surface, device/queue, and the window it
+/// renders into.
+///
+/// The engine stores this as a *non-send* resource (see `App::redraw` and
+/// friends in `context.rs`) rather than a regular `bevy_ecs` `Resource`,
+/// since the window/surface types aren't `Send`/`Sync` on every target --
+/// notably wasm32, where the window wraps a JS object.
+pub struct Graphics {
+    window: Rc<Window>,
+    surface: wgpu::Surface<'static>,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+}
+
+impl Graphics {
+    pub fn window_id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    pub fn request_redraw(&self) {
+        self.window.request_redraw();
+    }
+
+    pub fn resize(&mut self, size: PhysicalSize<u32>) {
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+        self.config.width = size.width;
+        self.config.height = size.height;
+        self.surface.configure(&self.device, &self.config);
+    }
+
+    pub fn draw(&mut self) {
+        let Ok(frame) = self.surface.get_current_texture() else {
+            return;
+        };
+        let view = frame
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let _pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("clear"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+        }
+
+        self.queue.submit(Some(encoder.finish()));
+        frame.present();
+    }
+}
+
+/// Sets up the wgpu surface/device/queue for `window` and sends the result
+/// back through `proxy` as `RuntimeRequest::GraphicsReady`.
+pub async fn create_graphics(window: Rc<Window>, proxy: EventLoopProxy<RuntimeRequest>) {
+    let size = window.inner_size();
+
+    let instance = wgpu::Instance::default();
+    let surface = instance
+        .create_surface(window.clone())
+        .expect("Failed to create a surface.");
+
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            compatible_surface: Some(&surface),
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to find a suitable adapter.");
+
+    let (device, queue) = adapter
+        .request_device(&wgpu::DeviceDescriptor::default(), None)
+        .await
+        .expect("Failed to create a device.");
+
+    let config = surface
+        .get_default_config(&adapter, size.width.max(1), size.height.max(1))
+        .expect("Surface unsupported by adapter.");
+    surface.configure(&device, &config);
+
+    let graphics = Graphics {
+        window,
+        surface,
+        device,
+        queue,
+        config,
+    };
+
+    let _ = proxy.send_event(RuntimeRequest::GraphicsReady(graphics));
+}
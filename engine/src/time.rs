@@ -0,0 +1,11 @@
+use bevy_ecs::prelude::*;
+
+/// Per-tick timing information, available to systems as a resource.
+#[derive(Resource, Default, Clone, Copy)]
+pub struct FrameTime {
+    /// Fixed simulation step size in seconds, constant while the engine runs.
+    pub dt: f32,
+    /// Leftover fraction (in `[0, 1)`) of a simulation step not yet consumed
+    /// by `update`, for interpolating render state between ticks.
+    pub alpha: f32,
+}
@@ -0,0 +1,82 @@
+use winit::dpi::PhysicalSize;
+use winit::window::{Window, WindowAttributes};
+
+/// Which canvas a wasm build should render into.
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Debug)]
+pub enum CanvasTarget {
+    /// Append a freshly created canvas to `<body>`.
+    Append,
+    /// Render into the existing canvas element with this id.
+    ExistingId(String),
+}
+
+/// Describes how the primary game window should be created.
+///
+/// Passed into [`EngineContext::new`](crate::EngineContext::new) so a game
+/// can configure its window (title, size, resizability, ...) without
+/// reaching into the engine's internal window-creation code.
+#[derive(Clone, Debug)]
+pub struct WindowConfig {
+    pub title: String,
+    pub inner_size: Option<PhysicalSize<u32>>,
+    pub min_inner_size: Option<PhysicalSize<u32>>,
+    pub max_inner_size: Option<PhysicalSize<u32>>,
+    pub resizable: bool,
+    pub decorations: bool,
+    #[cfg(target_arch = "wasm32")]
+    pub canvas_target: CanvasTarget,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            title: "Game".to_string(),
+            inner_size: None,
+            min_inner_size: None,
+            max_inner_size: None,
+            resizable: true,
+            decorations: true,
+            #[cfg(target_arch = "wasm32")]
+            canvas_target: CanvasTarget::Append,
+        }
+    }
+}
+
+impl WindowConfig {
+    pub(crate) fn to_attributes(&self) -> WindowAttributes {
+        let mut attrs = Window::default_attributes()
+            .with_title(self.title.clone())
+            .with_resizable(self.resizable)
+            .with_decorations(self.decorations);
+
+        if let Some(size) = self.inner_size {
+            attrs = attrs.with_inner_size(size);
+        }
+        if let Some(size) = self.min_inner_size {
+            attrs = attrs.with_min_inner_size(size);
+        }
+        if let Some(size) = self.max_inner_size {
+            attrs = attrs.with_max_inner_size(size);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            use wasm_bindgen::JsCast;
+            use winit::platform::web::WindowAttributesExtWebSys;
+
+            attrs = match &self.canvas_target {
+                CanvasTarget::Append => attrs.with_append(true),
+                CanvasTarget::ExistingId(id) => {
+                    let canvas = web_sys::window()
+                        .and_then(|w| w.document())
+                        .and_then(|d| d.get_element_by_id(id))
+                        .and_then(|el| el.dyn_into::<web_sys::HtmlCanvasElement>().ok());
+                    attrs.with_canvas(canvas)
+                }
+            };
+        }
+
+        attrs
+    }
+}
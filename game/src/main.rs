@@ -1,11 +1,20 @@
-use engine::EngineContext;
+use engine::bevy_ecs::prelude::*;
+use engine::{EngineContext, Game, WindowConfig};
 
 /// Temporary game state for testing.
 /// This will later hold your object model, systems, etc.
+struct MyGame;
 
+impl Game for MyGame {
+    fn build(&mut self, _world: &mut World, _update: &mut Schedule, _render: &mut Schedule) {}
+}
 
 fn main() {
     // ---- Create engine context (game owns this) ----
-    let ctx = EngineContext::new();
+    let window_config = WindowConfig {
+        title: "what".to_string(),
+        ..Default::default()
+    };
+    let ctx = EngineContext::new(MyGame, engine::context::DEFAULT_SIM_DT, window_config);
     ctx.run();
 }